@@ -0,0 +1,60 @@
+//! Assembles the `cargo` invocation that actually runs inside the build
+//! container, and forwards that process's output back to the host.
+
+use std::io;
+use std::process::{Child, Command, Stdio};
+use std::thread;
+
+use crate::shell::{adaptive_streams, MessageInfo};
+
+/// Builds the `cargo` invocation to run inside the container for
+/// `subcommand`, forwarding `args` verbatim. stdout/stderr are piped so the
+/// caller can forward them to the host with [`forward_child_output`].
+///
+/// The inner cargo/rustc can't see the host's TTY from inside the
+/// container, so when the host's diagnostic width is known, it's forwarded
+/// explicitly via `--diagnostic-width` so diagnostics wrap the same way
+/// they would have if rustc could query the terminal itself.
+pub fn cargo_command(msg_info: &MessageInfo, subcommand: &str, args: &[String]) -> Command {
+    let mut command = Command::new("cargo");
+    command.arg(subcommand);
+    command.args(args);
+    if let Some(width_arg) = msg_info.diagnostic_width_arg() {
+        command.arg(width_arg);
+    }
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+    command
+}
+
+/// Forwards a spawned container process's stdout/stderr to the host through
+/// the same adaptive writer used for cross's own messages, so that ANSI
+/// color emitted inside the container is handled as `msg_info.color_choice`
+/// says: under `ColorChoice::Auto` it is preserved when cross's own output
+/// is a terminal, and stripped the same way when it isn't.
+///
+/// The two streams are drained on separate threads rather than one after the
+/// other: a real build container writes to stdout and stderr concurrently,
+/// and copying stdout to completion first would block forever once stderr's
+/// pipe fills up and the child blocks on its own write to it.
+pub fn forward_child_output(child: &mut Child, msg_info: &MessageInfo) -> io::Result<()> {
+    let (mut stdout, mut stderr) = adaptive_streams(msg_info.color_choice);
+    let child_stdout = child.stdout.take();
+    let child_stderr = child.stderr.take();
+
+    thread::scope(|scope| {
+        let stdout_handle =
+            child_stdout.map(|mut child_stdout| scope.spawn(move || io::copy(&mut child_stdout, &mut stdout)));
+        let stderr_handle =
+            child_stderr.map(|mut child_stderr| scope.spawn(move || io::copy(&mut child_stderr, &mut stderr)));
+
+        if let Some(handle) = stdout_handle {
+            handle.join().expect("stdout forwarding thread panicked")?;
+        }
+        if let Some(handle) = stderr_handle {
+            handle.join().expect("stderr forwarding thread panicked")?;
+        }
+
+        Ok(())
+    })
+}
@@ -0,0 +1,2 @@
+pub mod cargo_command;
+pub mod shell;
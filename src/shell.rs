@@ -1,11 +1,11 @@
 // This file was adapted from:
 //   https://github.com/rust-lang/cargo/blob/ca4edabb28fc96fdf2a1d56fe3851831ac166f8a/src/cargo/core/shell.rs
 
+use std::collections::HashMap;
 use std::fmt;
 use std::io::{self, Write};
 
 use crate::errors::Result;
-use owo_colors::{self, OwoColorize};
 
 // get the prefix for stderr messages
 macro_rules! cross_prefix {
@@ -14,18 +14,16 @@ macro_rules! cross_prefix {
     };
 }
 
-// generate the color style
+// generate the color style, writing into an in-memory buffer rather than
+// straight to the stream: a whole message is assembled before it is ever
+// flushed, so it can't tear when interleaved with the container's streamed
+// output. `$style` is always applied unconditionally: whether it actually
+// reaches the terminal as color, gets translated for a legacy Windows
+// console, or is stripped entirely is decided once, by the `anstream`
+// writer the buffer is eventually flushed into.
 macro_rules! write_style {
-    ($stream:ident, $msg_info:expr, $message:expr $(, $style:ident)* $(,)?) => {{
-        match $msg_info.color_choice {
-            ColorChoice::Always => write!($stream, "{}", $message $(.$style())*),
-            ColorChoice::Never => write!($stream, "{}", $message),
-            ColorChoice::Auto => write!(
-                $stream,
-                "{}",
-                $message $(.if_supports_color($stream.owo(), |text| text.$style()))*
-            ),
-        }?;
+    ($buf:expr, $style:expr, $message:expr $(,)?) => {{
+        write!($buf, "{}{}{}", $style.render(), $message, $style.render_reset())?;
     }};
 }
 
@@ -34,31 +32,39 @@ macro_rules! message {
     // write a status message, which has the following format:
     //  "{status}: {message}"
     // both status and ':' are bold.
-    (@status $stream:ident, $status:expr, $message:expr, $color:ident, $msg_info:expr $(,)?) => {{
-        write_style!($stream, $msg_info, $status, bold, $color);
-        write_style!($stream, $msg_info, ":", bold);
+    //
+    // when the category's color is overridden via `CROSS_COLORS`, the raw
+    // SGR codes are used instead of the typed `anstyle` styling below.
+    //
+    // the whole message, including any pending erase-line sequence, is
+    // rendered into `buf` and flushed with a single `write_all`.
+    (@status $stream:expr, $erase:expr, $status:expr, $message:expr, $style:expr, $category:expr, $msg_info:expr $(,)?) => {{
+        let mut buf: Vec<u8> = $erase;
+        match $msg_info.category_style($category) {
+            Some(codes) => write!(buf, "\x1B[{codes}m{}\x1B[0m", $status)?,
+            None => write_style!(buf, $style, $status),
+        }
+        write_style!(buf, STYLE_BOLD, ":");
         match $message {
-            Some(message) => writeln!($stream, " {}", message)?,
-            None => write!($stream, " ")?,
+            Some(message) => writeln!(buf, " {}", message)?,
+            None => write!(buf, " ")?,
         }
+        $stream.write_all(&buf)?;
 
         Ok(())
     }};
-
-    (@status @name $name:ident, $status:expr, $message:expr, $color:ident, $msg_info:expr $(,)?) => {{
-        let mut stream = io::$name();
-        message!(@status stream, $status, $message, $color, $msg_info)
-    }};
 }
 
 // high-level interface to message
 macro_rules! status {
-    (@stderr $status:expr, $message:expr, $color:ident, $msg_info:expr $(,)?) => {{
-        message!(@status @name stderr, $status, $message, $color, $msg_info)
+    (@stderr $status:expr, $message:expr, $style:expr, $category:expr, $msg_info:expr $(,)?) => {{
+        let erase = $msg_info.take_stderr_erase();
+        message!(@status $msg_info.stderr, erase, $status, $message, $style, $category, $msg_info)
     }};
 
-    (@stdout $status:expr, $message:expr, $color:ident, $msg_info:expr  $(,)?) => {{
-        message!(@status @name stdout, $status, $message, $color, $msg_info)
+    (@stdout $status:expr, $message:expr, $style:expr, $category:expr, $msg_info:expr  $(,)?) => {{
+        let erase = $msg_info.take_stdout_erase();
+        message!(@status $msg_info.stdout, erase, $status, $message, $style, $category, $msg_info)
     }};
 }
 
@@ -90,22 +96,235 @@ pub enum ColorChoice {
     Auto,
 }
 
+impl From<ColorChoice> for anstream::ColorChoice {
+    fn from(choice: ColorChoice) -> Self {
+        match choice {
+            ColorChoice::Always => anstream::ColorChoice::Always,
+            ColorChoice::Never => anstream::ColorChoice::Never,
+            ColorChoice::Auto => anstream::ColorChoice::Auto,
+        }
+    }
+}
+
+/// The width of the terminal `MessageInfo` is writing diagnostics to.
+///
+/// cross runs the actual build inside a container, so the inner cargo/rustc
+/// can never see the host's TTY: this is used to thread the host-detected
+/// width through to the containerized cargo invocation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TtyWidth {
+    /// No stderr tty is attached, so no width is available.
+    NoTty,
+    /// The width was read directly from the attached terminal.
+    Known(usize),
+    /// The width was guessed, e.g. from the `COLUMNS` environment variable.
+    Guess(usize),
+}
+
+impl TtyWidth {
+    /// Returns the width to forward as `--diagnostic-width`, which should
+    /// only be set when the width is known for certain.
+    pub fn diagnostic_terminal_width(self) -> Option<usize> {
+        match self {
+            TtyWidth::Known(width) => Some(width),
+            TtyWidth::NoTty | TtyWidth::Guess(_) => None,
+        }
+    }
+
+    /// Returns the width to use for progress bars, which may fall back to a
+    /// guess when the real width isn't known.
+    pub fn progress_max_width(self) -> Option<usize> {
+        match self {
+            TtyWidth::Known(width) | TtyWidth::Guess(width) => Some(width),
+            TtyWidth::NoTty => None,
+        }
+    }
+}
+
+/// environment variable used to force a deterministic [`TtyWidth`] in
+/// integration tests, which don't run attached to a real terminal.
+const TEST_TERMINAL_WIDTH_ENV: &str = "__CROSS_TEST_TERMINAL_WIDTH";
+
+/// The width of the terminal attached to *stderr* specifically, since that's
+/// where cross's own diagnostics and the forwarded rustc diagnostics go.
+/// `terminal_size::terminal_size()` probes stdout first, which would report
+/// a width even when stderr itself is redirected to a file or pipe, so the
+/// stderr file descriptor/handle is queried directly instead.
+#[cfg(unix)]
+fn stderr_terminal_size() -> Option<usize> {
+    use std::os::unix::io::AsRawFd;
+
+    let (terminal_size::Width(width), _) =
+        terminal_size::terminal_size_using_fd(io::stderr().as_raw_fd())?;
+    Some(width as usize)
+}
+
+#[cfg(windows)]
+fn stderr_terminal_size() -> Option<usize> {
+    use std::os::windows::io::AsRawHandle;
+
+    let (terminal_size::Width(width), _) =
+        terminal_size::terminal_size_using_handle(io::stderr().as_raw_handle())?;
+    Some(width as usize)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn stderr_terminal_size() -> Option<usize> {
+    None
+}
+
+fn tty_width() -> TtyWidth {
+    if let Ok(width) = std::env::var(TEST_TERMINAL_WIDTH_ENV) {
+        return match width.parse() {
+            Ok(width) => TtyWidth::Known(width),
+            Err(_) => TtyWidth::NoTty,
+        };
+    }
+
+    if let Some(width) = stderr_terminal_size() {
+        return TtyWidth::Known(width);
+    }
+
+    // stderr isn't a tty (e.g. it's redirected to a file or piped): fall
+    // back to a guess from `$COLUMNS`, which most shells export for the
+    // foreground process even when its stdio isn't a terminal.
+    match std::env::var("COLUMNS").ok().and_then(|columns| columns.parse().ok()) {
+        Some(width) => TtyWidth::Guess(width),
+        None => TtyWidth::NoTty,
+    }
+}
+
+/// A category of message whose style can be overridden via `CROSS_COLORS`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Category {
+    Error,
+    Warning,
+    Note,
+    Status,
+    Info,
+}
+
+impl Category {
+    fn parse(name: &str) -> Option<Category> {
+        match name {
+            "error" => Some(Category::Error),
+            "warning" => Some(Category::Warning),
+            "note" => Some(Category::Note),
+            "status" => Some(Category::Status),
+            "info" => Some(Category::Info),
+            _ => None,
+        }
+    }
+}
+
+/// the environment variable used to override the built-in message colors.
+const CROSS_COLORS_ENV: &str = "CROSS_COLORS";
+
+/// Parses the `CROSS_COLORS` environment variable, which uses the same
+/// `category=sgr:category=sgr:...` grammar as `GCC_COLORS`, e.g.
+/// `error=01;31:warning=01;33:note=01;36`. Invalid entries are reported as
+/// warnings rather than aborting parsing.
+fn parse_cross_colors(var: &str) -> (HashMap<Category, String>, Vec<String>) {
+    let mut overrides = HashMap::new();
+    let mut warnings = Vec::new();
+
+    for entry in var.split(':').filter(|entry| !entry.is_empty()) {
+        let Some((category, sgr)) = entry.split_once('=') else {
+            warnings.push(format!(
+                "ignoring invalid {CROSS_COLORS_ENV} entry `{entry}`: expected `category=sgr`"
+            ));
+            continue;
+        };
+        let Some(category) = Category::parse(category) else {
+            warnings.push(format!(
+                "ignoring invalid {CROSS_COLORS_ENV} entry `{entry}`: unknown category `{category}`"
+            ));
+            continue;
+        };
+        let is_valid_sgr = !sgr.is_empty()
+            && sgr
+                .split(';')
+                .all(|code| !code.is_empty() && code.bytes().all(|b| b.is_ascii_digit()));
+        if !is_valid_sgr {
+            warnings.push(format!(
+                "ignoring invalid {CROSS_COLORS_ENV} entry `{entry}`: `{sgr}` is not a semicolon-separated list of SGR codes"
+            ));
+            continue;
+        }
+        overrides.insert(category, sgr.to_string());
+    }
+
+    (overrides, warnings)
+}
+
+const STYLE_BOLD: anstyle::Style = anstyle::Style::new().bold();
+const STYLE_ERROR: anstyle::Style = STYLE_BOLD.fg_color(Some(anstyle::Color::Ansi(anstyle::AnsiColor::Red)));
+const STYLE_WARNING: anstyle::Style =
+    STYLE_BOLD.fg_color(Some(anstyle::Color::Ansi(anstyle::AnsiColor::Yellow)));
+const STYLE_NOTE: anstyle::Style = STYLE_BOLD.fg_color(Some(anstyle::Color::Ansi(anstyle::AnsiColor::Cyan)));
+const STYLE_ARG: anstyle::Style =
+    anstyle::Style::new().fg_color(Some(anstyle::Color::Ansi(anstyle::AnsiColor::Yellow)));
+const STYLE_HELP: anstyle::Style =
+    anstyle::Style::new().fg_color(Some(anstyle::Color::Ansi(anstyle::AnsiColor::Green)));
+
+fn adaptive_stdout(color_choice: ColorChoice) -> anstream::AutoStream<io::Stdout> {
+    anstream::AutoStream::new(io::stdout(), color_choice.into())
+}
+
+fn adaptive_stderr(color_choice: ColorChoice) -> anstream::AutoStream<io::Stderr> {
+    anstream::AutoStream::new(io::stderr(), color_choice.into())
+}
+
+/// Builds an adaptive stdout/stderr pair for `color_choice`: under
+/// `ColorChoice::Auto`, ANSI escapes are stripped when the destination isn't
+/// a real terminal (`Always` keeps them, `Never` always strips them), and
+/// escapes that are kept are translated for legacy Windows consoles that
+/// lack VT processing. Beyond cross's own
+/// messages, code that forwards another process's output (e.g. the
+/// container runtime) should reuse these so that color produced inside the
+/// container is handled the same way.
+pub fn adaptive_streams(
+    color_choice: ColorChoice,
+) -> (anstream::AutoStream<io::Stdout>, anstream::AutoStream<io::Stderr>) {
+    (adaptive_stdout(color_choice), adaptive_stderr(color_choice))
+}
+
 // Should simplify the APIs a lot.
-#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MessageInfo {
     pub color_choice: ColorChoice,
     pub verbosity: Verbosity,
     pub stdout_needs_erase: bool,
     pub stderr_needs_erase: bool,
+    color_overrides: HashMap<Category, String>,
+    terminal_width: TtyWidth,
+    stdout: anstream::AutoStream<io::Stdout>,
+    stderr: anstream::AutoStream<io::Stderr>,
+}
+
+impl fmt::Debug for MessageInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MessageInfo")
+            .field("color_choice", &self.color_choice)
+            .field("verbosity", &self.verbosity)
+            .field("stdout_needs_erase", &self.stdout_needs_erase)
+            .field("stderr_needs_erase", &self.stderr_needs_erase)
+            .field("color_overrides", &self.color_overrides)
+            .field("terminal_width", &self.terminal_width)
+            .finish_non_exhaustive()
+    }
 }
 
 impl MessageInfo {
-    pub const fn new(color_choice: ColorChoice, verbosity: Verbosity) -> MessageInfo {
+    pub fn new(color_choice: ColorChoice, verbosity: Verbosity) -> MessageInfo {
         MessageInfo {
             color_choice,
             verbosity,
             stdout_needs_erase: false,
             stderr_needs_erase: false,
+            color_overrides: HashMap::new(),
+            terminal_width: tty_width(),
+            stdout: adaptive_stdout(color_choice),
+            stderr: adaptive_stderr(color_choice),
         }
     }
 
@@ -113,18 +332,58 @@ impl MessageInfo {
         let color_choice = get_color_choice(color)?;
         let verbosity = get_verbosity(color_choice, verbose, quiet)?;
 
-        Ok(MessageInfo {
+        let mut info = MessageInfo {
             color_choice,
             verbosity,
             stdout_needs_erase: false,
             stderr_needs_erase: false,
-        })
+            color_overrides: HashMap::new(),
+            terminal_width: tty_width(),
+            stdout: adaptive_stdout(color_choice),
+            stderr: adaptive_stderr(color_choice),
+        };
+
+        if let Ok(var) = std::env::var(CROSS_COLORS_ENV) {
+            let (overrides, warnings) = parse_cross_colors(&var);
+            info.color_overrides = overrides;
+            for warning in warnings {
+                info.warn(warning)?;
+            }
+        }
+
+        Ok(info)
+    }
+
+    /// the raw SGR codes to use for `category`, if the user overrode it via
+    /// `CROSS_COLORS`. Whether that ends up visible is decided by the
+    /// adaptive stream it's written to, not here.
+    fn category_style(&self, category: Category) -> Option<&str> {
+        self.color_overrides.get(&category).map(String::as_str)
     }
 
     pub fn is_verbose(&self) -> bool {
         self.verbosity.verbose()
     }
 
+    /// the width known for the host terminal, if any.
+    pub fn diagnostic_terminal_width(&self) -> Option<usize> {
+        self.terminal_width.diagnostic_terminal_width()
+    }
+
+    /// the width to wrap progress bars at, which may be a guess.
+    pub fn progress_max_width(&self) -> Option<usize> {
+        self.terminal_width.progress_max_width()
+    }
+
+    /// the `--diagnostic-width=<n>` argument to forward to the inner
+    /// cargo/rustc invocation running inside the container, so its
+    /// diagnostics wrap at the same width as the host terminal. `None` when
+    /// the host width isn't known for certain.
+    pub fn diagnostic_width_arg(&self) -> Option<String> {
+        self.diagnostic_terminal_width()
+            .map(|width| format!("--diagnostic-width={width}"))
+    }
+
     fn as_verbosity<T, C: Fn(&mut MessageInfo) -> T>(&mut self, call: C, new: Verbosity) -> T {
         let old = self.verbosity;
         self.verbosity = new;
@@ -146,25 +405,23 @@ impl MessageInfo {
         self.as_verbosity(call, Verbosity::Verbose)
     }
 
-    fn erase_line<S: Stream + Write>(&mut self, stream: &mut S) -> Result<()> {
-        // this is the Erase in Line sequence
-        stream.write_all(b"\x1B[K").map_err(Into::into)
-    }
-
-    fn stdout_check_erase(&mut self) -> Result<()> {
-        if self.stdout_needs_erase {
-            self.erase_line(&mut io::stdout())?;
-            self.stdout_needs_erase = false;
+    // returns the Erase in Line sequence if a progress line is pending, so
+    // it can be prepended into the same buffer as the message that erases
+    // it: erase and replacement are then flushed as a single atomic write.
+    fn take_stdout_erase(&mut self) -> Vec<u8> {
+        if std::mem::take(&mut self.stdout_needs_erase) {
+            b"\x1B[K".to_vec()
+        } else {
+            Vec::new()
         }
-        Ok(())
     }
 
-    fn stderr_check_erase(&mut self) -> Result<()> {
-        if self.stderr_needs_erase {
-            self.erase_line(&mut io::stderr())?;
-            self.stderr_needs_erase = false;
+    fn take_stderr_erase(&mut self) -> Vec<u8> {
+        if std::mem::take(&mut self.stderr_needs_erase) {
+            b"\x1B[K".to_vec()
+        } else {
+            Vec::new()
         }
-        Ok(())
     }
 
     /// prints a red 'error' message and terminates.
@@ -175,8 +432,7 @@ impl MessageInfo {
 
     /// prints a red 'error' message.
     pub fn error<T: fmt::Display>(&mut self, message: T) -> Result<()> {
-        self.stderr_check_erase()?;
-        status!(@stderr cross_prefix!("error"), Some(&message), red, self)
+        status!(@stderr cross_prefix!("error"), Some(&message), STYLE_ERROR, Category::Error, self)
     }
 
     /// prints an amber 'warning' message.
@@ -186,7 +442,8 @@ impl MessageInfo {
             _ => status!(@stderr
                 cross_prefix!("warning"),
                 Some(&message),
-                yellow,
+                STYLE_WARNING,
+                Category::Warning,
                 self,
             ),
         }
@@ -196,7 +453,7 @@ impl MessageInfo {
     pub fn note<T: fmt::Display>(&mut self, message: T) -> Result<()> {
         match self.verbosity {
             Verbosity::Quiet => Ok(()),
-            _ => status!(@stderr cross_prefix!("note"), Some(&message), cyan, self),
+            _ => status!(@stderr cross_prefix!("note"), Some(&message), STYLE_NOTE, Category::Note, self),
         }
     }
 
@@ -204,7 +461,11 @@ impl MessageInfo {
         match self.verbosity {
             Verbosity::Quiet => Ok(()),
             _ => {
-                eprintln!("{}", message);
+                let style = self.category_style(Category::Status).map(str::to_string);
+                match style {
+                    Some(codes) => writeln!(self.stderr, "\x1B[{codes}m{}\x1B[0m", message)?,
+                    None => writeln!(self.stderr, "{}", message)?,
+                }
                 Ok(())
             }
         }
@@ -212,8 +473,9 @@ impl MessageInfo {
 
     /// prints a high-priority message to stdout.
     pub fn print<T: fmt::Display>(&mut self, message: T) -> Result<()> {
-        self.stdout_check_erase()?;
-        println!("{}", message);
+        let mut buf = self.take_stdout_erase();
+        writeln!(buf, "{}", message)?;
+        self.stdout.write_all(&buf)?;
         Ok(())
     }
 
@@ -222,7 +484,11 @@ impl MessageInfo {
         match self.verbosity {
             Verbosity::Quiet => Ok(()),
             _ => {
-                println!("{}", message);
+                let style = self.category_style(Category::Info).map(str::to_string);
+                match style {
+                    Some(codes) => writeln!(self.stdout, "\x1B[{codes}m{}\x1B[0m", message)?,
+                    None => writeln!(self.stdout, "{}", message)?,
+                }
                 Ok(())
             }
         }
@@ -233,7 +499,7 @@ impl MessageInfo {
         match self.verbosity {
             Verbosity::Quiet | Verbosity::Normal => Ok(()),
             _ => {
-                println!("{}", message);
+                writeln!(self.stdout, "{}", message)?;
                 Ok(())
             }
         }
@@ -245,24 +511,20 @@ impl MessageInfo {
     }
 
     fn error_usage<T: fmt::Display>(&mut self, arg: T) -> Result<()> {
-        let mut stream = io::stderr();
-        write_style!(stream, self, cross_prefix!("error"), bold, red);
-        write_style!(stream, self, ":", bold);
-        write_style!(stream, self, " The argument '");
-        write_style!(stream, self, arg, yellow);
-        write_style!(stream, self, "' requires a value but none was supplied\n");
-        write_style!(stream, self, "Usage:\n");
-        write_style!(
-            stream,
-            self,
-            "    cross [+toolchain] [OPTIONS] [SUBCOMMAND]\n"
-        );
-        write_style!(stream, self, "\n");
-        write_style!(stream, self, "For more information try ");
-        write_style!(stream, self, "--help", green);
-        write_style!(stream, self, "\n");
-
-        stream.flush()?;
+        let mut buf: Vec<u8> = Vec::new();
+        write_style!(buf, STYLE_ERROR, cross_prefix!("error"));
+        write_style!(buf, STYLE_BOLD, ":");
+        write!(buf, " The argument '")?;
+        write_style!(buf, STYLE_ARG, arg);
+        writeln!(buf, "' requires a value but none was supplied")?;
+        writeln!(buf, "Usage:")?;
+        writeln!(buf, "    cross [+toolchain] [OPTIONS] [SUBCOMMAND]")?;
+        writeln!(buf)?;
+        write!(buf, "For more information try ")?;
+        write_style!(buf, STYLE_HELP, "--help");
+        writeln!(buf)?;
+
+        self.stderr.write_all(&buf)?;
 
         Ok(())
     }
@@ -315,34 +577,6 @@ fn get_verbosity(color_choice: ColorChoice, verbose: bool, quiet: bool) -> Resul
     }
 }
 
-pub trait Stream {
-    const TTY: atty::Stream;
-    const OWO: owo_colors::Stream;
-
-    fn is_atty() -> bool {
-        atty::is(Self::TTY)
-    }
-
-    fn owo(&self) -> owo_colors::Stream {
-        Self::OWO
-    }
-}
-
-impl Stream for io::Stdin {
-    const TTY: atty::Stream = atty::Stream::Stdin;
-    const OWO: owo_colors::Stream = owo_colors::Stream::Stdin;
-}
-
-impl Stream for io::Stdout {
-    const TTY: atty::Stream = atty::Stream::Stdout;
-    const OWO: owo_colors::Stream = owo_colors::Stream::Stdout;
-}
-
-impl Stream for io::Stderr {
-    const TTY: atty::Stream = atty::Stream::Stderr;
-    const OWO: owo_colors::Stream = owo_colors::Stream::Stderr;
-}
-
 pub fn default_ident() -> usize {
     cross_prefix!("").len()
 }
@@ -353,3 +587,71 @@ pub fn indent(message: &str, spaces: usize) -> String {
         .map(|s| format!("{:spaces$}{s}", ""))
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tty_width_known_reports_diagnostic_and_progress_width() {
+        let width = TtyWidth::Known(120);
+        assert_eq!(width.diagnostic_terminal_width(), Some(120));
+        assert_eq!(width.progress_max_width(), Some(120));
+    }
+
+    #[test]
+    fn tty_width_guess_reports_progress_width_but_not_diagnostic_width() {
+        let width = TtyWidth::Guess(80);
+        assert_eq!(width.diagnostic_terminal_width(), None);
+        assert_eq!(width.progress_max_width(), Some(80));
+    }
+
+    #[test]
+    fn tty_width_no_tty_reports_neither_width() {
+        let width = TtyWidth::NoTty;
+        assert_eq!(width.diagnostic_terminal_width(), None);
+        assert_eq!(width.progress_max_width(), None);
+    }
+
+    #[test]
+    fn tty_width_honors_test_override_env_var() {
+        std::env::set_var(TEST_TERMINAL_WIDTH_ENV, "137");
+        assert_eq!(tty_width(), TtyWidth::Known(137));
+
+        std::env::set_var(TEST_TERMINAL_WIDTH_ENV, "not-a-number");
+        assert_eq!(tty_width(), TtyWidth::NoTty);
+
+        std::env::remove_var(TEST_TERMINAL_WIDTH_ENV);
+    }
+
+    #[test]
+    fn parse_cross_colors_accepts_a_valid_override() {
+        let (overrides, warnings) = parse_cross_colors("error=01;31:note=36");
+        assert_eq!(overrides.get(&Category::Error).map(String::as_str), Some("01;31"));
+        assert_eq!(overrides.get(&Category::Note).map(String::as_str), Some("36"));
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn parse_cross_colors_warns_on_unknown_category() {
+        let (overrides, warnings) = parse_cross_colors("bogus=01;31");
+        assert!(overrides.is_empty());
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("bogus"));
+    }
+
+    #[test]
+    fn parse_cross_colors_warns_on_non_numeric_sgr() {
+        let (overrides, warnings) = parse_cross_colors("error=bold");
+        assert!(overrides.is_empty());
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("bold"));
+    }
+
+    #[test]
+    fn parse_cross_colors_ignores_empty_entries() {
+        let (overrides, warnings) = parse_cross_colors("error=01;31::warning=01;33");
+        assert_eq!(overrides.len(), 2);
+        assert!(warnings.is_empty());
+    }
+}